@@ -0,0 +1,83 @@
+use ethers::middleware::nonce_manager::NonceManagerMiddleware;
+use ethers::middleware::signer::SignerMiddleware;
+use ethers::prelude::*;
+use ethers::providers::{Provider, Ws};
+use ethers::signers::{LocalWallet, Signer};
+use std::env;
+use std::sync::Arc;
+use tokio::sync::watch;
+use anyhow::{Context, Result};
+
+use crate::gas::FeeEstimate;
+use crate::transport::ProviderWatch;
+use crate::ApexFlashAggregator;
+
+/// Fully composed client: nonce manager -> signer -> provider. Fee pricing is
+/// handled by our own `eth_feeHistory`-based estimator (see `gas.rs`) and set
+/// directly on each call, so no gas-oracle middleware layer is needed here.
+pub type ExecutionClient = NonceManagerMiddleware<SignerMiddleware<Arc<Provider<Ws>>, LocalWallet>>;
+
+/// Builds the signer + nonce manager middleware stack around a raw provider,
+/// using `PRIVATE_KEY` from the environment.
+pub async fn build_execution_client(provider: Arc<Provider<Ws>>) -> Result<Arc<ExecutionClient>> {
+    let chain_id = provider.get_chainid().await?.as_u64();
+
+    let private_key = env::var("PRIVATE_KEY").context("PRIVATE_KEY must be set to execute trades")?;
+    let wallet: LocalWallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
+    let address = wallet.address();
+
+    let signer_client = SignerMiddleware::new(provider, wallet);
+    let nonce_client = NonceManagerMiddleware::new(signer_client, address);
+
+    Ok(Arc::new(nonce_client))
+}
+
+/// Live handle to the current `ApexFlashAggregator` contract, republished
+/// every time the underlying WS provider reconnects.
+pub type ContractWatch = watch::Receiver<Arc<ApexFlashAggregator<ExecutionClient>>>;
+
+/// Builds the initial execution client/contract and spawns a background task
+/// that rebuilds both whenever `provider_rx` publishes a freshly reconnected
+/// provider, so callers never submit `executeArbitrage` over a dead socket.
+pub async fn watch_contract(mut provider_rx: ProviderWatch, address: Address) -> Result<ContractWatch> {
+    let initial_provider = provider_rx.borrow().clone();
+    let initial_client = build_execution_client(initial_provider).await?;
+    let (tx, rx) = watch::channel(Arc::new(ApexFlashAggregator::new(address, initial_client)));
+
+    tokio::spawn(async move {
+        while provider_rx.changed().await.is_ok() {
+            let provider = provider_rx.borrow().clone();
+            match build_execution_client(provider).await {
+                Ok(client) => {
+                    let _ = tx.send(Arc::new(ApexFlashAggregator::new(address, client)));
+                    log::info!("execution client rebuilt after provider reconnect");
+                }
+                Err(e) => log::error!("failed to rebuild execution client after reconnect: {e}"),
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Builds and submits the `executeArbitrage(token, amount, data)` transaction
+/// using the supplied EIP-1559 fee recommendation.
+pub async fn execute_arbitrage(
+    contract: &ApexFlashAggregator<ExecutionClient>,
+    token: Address,
+    amount: U256,
+    data: Bytes,
+    fees: FeeEstimate,
+) -> Result<H256> {
+    let mut call = contract
+        .execute_arbitrage(token, amount, data)
+        .gas(500_000u64);
+    call.tx.set_max_fee_per_gas(fees.max_fee_per_gas);
+    call.tx.set_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+
+    let pending_tx = call.send().await?;
+    let tx_hash = pending_tx.tx_hash();
+    println!("📤 Submitted executeArbitrage tx: {:?}", tx_hash);
+
+    Ok(tx_hash)
+}
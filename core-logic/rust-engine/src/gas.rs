@@ -0,0 +1,139 @@
+use ethers::prelude::*;
+use ethers::providers::{Provider, Ws};
+use anyhow::Result;
+
+/// Number of trailing blocks sampled from `eth_feeHistory` when estimating
+/// the next-block fee.
+const FEE_HISTORY_WINDOW: u64 = 20;
+
+/// Priority-fee percentile requested per block (median tip paid).
+const REWARD_PERCENTILE: f64 = 50.0;
+
+/// Priority-fee percentile used as the tip once recent blocks are contested
+/// (see [`FULL_BLOCK_GAS_RATIO`]), i.e. when it is worth outbidding.
+const AGGRESSIVE_REWARD_PERCENTILE: f64 = 90.0;
+
+/// A block is considered "full" (competitive) once its gas-used ratio
+/// crosses this fraction.
+const FULL_BLOCK_GAS_RATIO: f64 = 0.9;
+
+/// Recommended EIP-1559 fee parameters for the next block.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeEstimate {
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+}
+
+/// Queries `eth_feeHistory` over the last [`FEE_HISTORY_WINDOW`] blocks and
+/// derives a recommended `maxPriorityFeePerGas`/`maxFeePerGas` for the next
+/// block, bumping the tip aggressively when recent blocks are running full.
+pub async fn estimate_fees(client: &Provider<Ws>) -> Result<FeeEstimate> {
+    let history = client
+        .fee_history(
+            FEE_HISTORY_WINDOW,
+            BlockNumber::Latest,
+            &[REWARD_PERCENTILE, AGGRESSIVE_REWARD_PERCENTILE],
+        )
+        .await?;
+
+    Ok(compute_fee_estimate(
+        &history.base_fee_per_gas,
+        &history.gas_used_ratio,
+        &history.reward,
+    ))
+}
+
+/// Pure percentile/contested-block math pulled out of [`estimate_fees`] so it
+/// can be exercised without a live `eth_feeHistory` call.
+fn compute_fee_estimate(
+    base_fee_per_gas: &[U256],
+    gas_used_ratio: &[f64],
+    reward: &[Vec<U256>],
+) -> FeeEstimate {
+    let latest_base_fee = *base_fee_per_gas.last().unwrap_or(&U256::zero());
+
+    let full_blocks = gas_used_ratio
+        .iter()
+        .filter(|ratio| **ratio >= FULL_BLOCK_GAS_RATIO)
+        .count();
+    let contested = full_blocks * 2 > gas_used_ratio.len();
+
+    let percentile_index = if contested { 1 } else { 0 };
+    let priority_fees: Vec<U256> = reward
+        .iter()
+        .filter_map(|rewards| rewards.get(percentile_index).copied())
+        .collect();
+
+    let max_priority_fee_per_gas = if priority_fees.is_empty() {
+        U256::from(2_000_000_000u64) // 2 gwei fallback
+    } else {
+        average(&priority_fees)
+    };
+
+    // Standard EIP-1559 headroom: 2x the latest base fee covers five
+    // consecutive full blocks (+12.5% each at most), plus the priority tip.
+    let max_fee_per_gas = latest_base_fee
+        .saturating_mul(U256::from(2u64))
+        .saturating_add(max_priority_fee_per_gas);
+
+    FeeEstimate {
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+    }
+}
+
+fn average(values: &[U256]) -> U256 {
+    let sum: U256 = values.iter().fold(U256::zero(), |acc, v| acc + v);
+    sum / U256::from(values.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_blocks_use_the_median_percentile_and_1_5gwei_tip() {
+        let base_fee_per_gas = vec![U256::from(10_000_000_000u64), U256::from(12_000_000_000u64)];
+        let gas_used_ratio = vec![0.2, 0.3];
+        let reward = vec![
+            vec![U256::from(1_000_000_000u64), U256::from(3_000_000_000u64)],
+            vec![U256::from(2_000_000_000u64), U256::from(4_000_000_000u64)],
+        ];
+
+        let fees = compute_fee_estimate(&base_fee_per_gas, &gas_used_ratio, &reward);
+
+        // Not contested: median (index 0) percentile averaged.
+        assert_eq!(fees.max_priority_fee_per_gas, U256::from(1_500_000_000u64));
+        assert_eq!(
+            fees.max_fee_per_gas,
+            U256::from(12_000_000_000u64) * 2 + U256::from(1_500_000_000u64)
+        );
+    }
+
+    #[test]
+    fn contested_blocks_switch_to_the_aggressive_percentile() {
+        let base_fee_per_gas = vec![U256::from(10_000_000_000u64)];
+        let gas_used_ratio = vec![0.95, 0.92, 0.1];
+        let reward = vec![
+            vec![U256::from(1_000_000_000u64), U256::from(5_000_000_000u64)],
+            vec![U256::from(1_000_000_000u64), U256::from(7_000_000_000u64)],
+        ];
+
+        let fees = compute_fee_estimate(&base_fee_per_gas, &gas_used_ratio, &reward);
+
+        // 2/3 full blocks crosses the majority threshold -> aggressive (index 1) percentile.
+        assert_eq!(fees.max_priority_fee_per_gas, U256::from(6_000_000_000u64));
+    }
+
+    #[test]
+    fn empty_reward_history_falls_back_to_2gwei() {
+        let fees = compute_fee_estimate(&[U256::from(10_000_000_000u64)], &[], &[]);
+        assert_eq!(fees.max_priority_fee_per_gas, U256::from(2_000_000_000u64));
+    }
+
+    #[test]
+    fn missing_base_fee_history_treated_as_zero() {
+        let fees = compute_fee_estimate(&[], &[], &[vec![U256::from(1_000_000_000u64)]]);
+        assert_eq!(fees.max_fee_per_gas, U256::from(1_000_000_000u64));
+    }
+}
@@ -1,58 +1,122 @@
-use ethers::prelude::*;
-use ethers::providers::{Provider, Ws};
-use std::sync::Arc;
-use dotenv::dotenv;
-use std::env;
-use anyhow::Result;
-
-// ApexFlashAggregator Address (Deployed)
-const AGGREGATOR_ADDRESS: &str = "0x82BBAA3B0982D88741B275aE1752DB85CAfe3c65";
-
-abigen!(
-    ApexFlashAggregator,
-    r#"[
-        function executeArbitrage(address token, uint256 amount, bytes calldata data) external
-        function owner() view returns (address)
-    ]"#
-);
-
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenv().ok();
-    env_logger::init();
-
-    println!("🚀 AINEX RUST ENGINE: INITIALIZING...");
-
-    let rpc_url = env::var("ETH_RPC_URL").unwrap_or_else(|_| "ws://localhost:8545".to_string());
-    println!("🔌 Connecting to: {}", rpc_url);
-
-    let provider = Provider::<Ws>::connect(rpc_url).await?;
-    let client = Arc::new(provider);
-
-    let address: Address = AGGREGATOR_ADDRESS.parse()?;
-    let contract = ApexFlashAggregator::new(address, client.clone());
-
-    println!("✅ Connected to ApexFlashAggregator at {:?}", address);
-    println!("🎧 Listening for new blocks...");
-
-    let mut stream = client.subscribe_blocks().await?;
-
-    while let Some(block) = stream.next().await {
-        println!("📦 New Block: {:?} | Timestamp: {}", block.number.unwrap(), block.timestamp);
-        
-        // HIGH-FREQUENCY LOGIC HERE
-        // 1. Scan mempool (not implemented in this basic loop)
-        // 2. Check for arb opportunities
-        // 3. Execute via contract
-        
-        // Mock Execution Trigger
-        if block.timestamp % 100 < 5 { // 5% chance per block
-            println!("⚡ Opportunity Detected! Executing Strategy...");
-            // In a real scenario, we would construct the payload and call executeArbitrage
-            // let tx = contract.execute_arbitrage(...);
-            // tx.send().await?;
-        }
-    }
-
-    Ok(())
-}
+use ethers::prelude::*;
+use ethers::providers::{Provider, Ws};
+use std::collections::HashSet;
+use std::sync::Arc;
+use dotenv::dotenv;
+use std::env;
+use anyhow::Result;
+
+mod execution;
+mod gas;
+mod mempool;
+mod pools;
+mod transport;
+
+// ApexFlashAggregator Address (Deployed)
+const AGGREGATOR_ADDRESS: &str = "0x82BBAA3B0982D88741B275aE1752DB85CAfe3c65";
+
+abigen!(
+    ApexFlashAggregator,
+    r#"[
+        function executeArbitrage(address token, uint256 amount, bytes calldata data) external
+        function owner() view returns (address)
+    ]"#
+);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    env_logger::init();
+
+    println!("🚀 AINEX RUST ENGINE: INITIALIZING...");
+
+    let endpoints = transport::Endpoints::from_env();
+    println!("🔌 Connecting to: {}", endpoints.primary_ws);
+
+    let provider_rx = transport::connect(endpoints).await?;
+
+    let address: Address = AGGREGATOR_ADDRESS.parse()?;
+
+    let contract_rx = execution::watch_contract(provider_rx.clone(), address).await?;
+
+    println!("✅ Connected to ApexFlashAggregator at {:?}", address);
+
+    let watched_pools = watched_pools_from_env();
+    if !watched_pools.is_empty() {
+        let mempool_rx = provider_rx.clone();
+        let mempool_contract_rx = contract_rx.clone();
+        let mempool_pools = watched_pools.clone();
+        tokio::spawn(async move {
+            run_with_reconnect(mempool_rx, move |client| {
+                mempool::watch_pending_txs(client, mempool_contract_rx.clone(), mempool_pools.clone())
+            })
+            .await;
+        });
+
+        let pool_rx = provider_rx.clone();
+        let pool_contract_rx = contract_rx.clone();
+        let pool_addresses: Vec<Address> = watched_pools.into_iter().collect();
+        let reserves: pools::ReserveMap = Arc::new(tokio::sync::RwLock::new(Default::default()));
+        tokio::spawn(async move {
+            run_with_reconnect(pool_rx, move |client| {
+                pools::stream_pool_events(
+                    client,
+                    pool_contract_rx.clone(),
+                    pool_addresses.clone(),
+                    reserves.clone(),
+                )
+            })
+            .await;
+        });
+    }
+
+    println!("🎧 Listening for new blocks...");
+
+    run_with_reconnect(provider_rx, |client| async move {
+        let mut stream = client.subscribe_blocks().await?;
+        while let Some(block) = stream.next().await {
+            println!("📦 New Block: {:?} | Timestamp: {}", block.number.unwrap(), block.timestamp);
+        }
+        Ok(())
+    })
+    .await;
+
+    Ok(())
+}
+
+/// Retry delay used when a subscription body ends on its own (e.g. a
+/// transient RPC error) rather than because the provider actually reconnected.
+const SUBSCRIPTION_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Drives `subscription` against whatever provider is currently published on
+/// `provider_rx`, and re-enters it with the current client as soon as either
+/// the connection manager reconnects or a short retry delay elapses —
+/// whichever comes first. The delay matters because most errors inside a
+/// subscription body (a rate limit, one failed `eth_call`) leave the socket
+/// itself healthy, so waiting on `provider_rx.changed()` alone would never
+/// fire and the watcher would be stuck forever.
+async fn run_with_reconnect<F, Fut>(mut provider_rx: transport::ProviderWatch, mut subscription: F)
+where
+    F: FnMut(Arc<Provider<Ws>>) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    loop {
+        let client = provider_rx.borrow().clone();
+        if let Err(e) = subscription(client).await {
+            log::warn!("subscription ended: {e}, retrying...");
+        }
+        tokio::select! {
+            _ = provider_rx.changed() => {}
+            _ = tokio::time::sleep(SUBSCRIPTION_RETRY_DELAY) => {}
+        }
+    }
+}
+
+/// Reads `WATCHED_POOLS` (comma-separated addresses) from the environment.
+fn watched_pools_from_env() -> HashSet<Address> {
+    env::var("WATCHED_POOLS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| s.trim().parse::<Address>().ok())
+        .collect()
+}
@@ -0,0 +1,322 @@
+use ethers::prelude::*;
+use ethers::providers::{Provider, Ws};
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use anyhow::Result;
+use serde_json::json;
+
+use crate::execution::{self, ContractWatch};
+use crate::gas;
+use crate::pools::{self, TokenCache};
+
+/// Fraction of the pool's post-tx depth (in the traded token) to size the
+/// front-run/back-run at, mirroring the sizing used for cross-pool arbs.
+const TRADE_SIZE_DEPTH_DIVISOR: u64 = 100; // 1%
+
+/// A pool/token address we actively track for reserve-changing pending txs.
+pub type WatchedPools = HashSet<Address>;
+
+/// Reserves for a pool, decoded from a UniswapV2-style packed storage slot
+/// (reserve0: uint112, reserve1: uint112, blockTimestampLast: uint32).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reserves {
+    pub reserve0: U256,
+    pub reserve1: U256,
+}
+
+/// A predicted front-run/back-run opportunity surfaced before the triggering
+/// tx is mined.
+#[derive(Debug, Clone)]
+pub struct PendingOpportunity {
+    pub pool: Address,
+    pub tx_hash: H256,
+    pub pre: Reserves,
+    pub post: Reserves,
+}
+
+const RESERVES_SLOT: u64 = 8;
+
+/// Unpacks a single packed storage slot into (reserve0, reserve1).
+fn decode_reserves_slot(slot: H256) -> Reserves {
+    let bytes = slot.as_bytes();
+    // Storage is big-endian; reserve1 (112 bits) sits above reserve0 (112 bits),
+    // with blockTimestampLast (32 bits) as the high bits.
+    let reserve1 = U256::from_big_endian(&bytes[4..18]);
+    let reserve0 = U256::from_big_endian(&bytes[18..32]);
+    Reserves { reserve0, reserve1 }
+}
+
+/// Subscribes to the pending-tx mempool and, for each pending tx sent
+/// directly to a watched pool, runs a stateDiff trace against the pending block to predict
+/// post-tx reserves ahead of inclusion.
+pub async fn watch_pending_txs(
+    client: Arc<Provider<Ws>>,
+    contract_rx: ContractWatch,
+    watched_pools: WatchedPools,
+) -> Result<()> {
+    let mut stream = client.subscribe_pending_txs().await?;
+    println!("🕵️  Watching mempool for {} pool(s)...", watched_pools.len());
+
+    let token_cache: TokenCache = Arc::new(RwLock::new(HashMap::new()));
+
+    while let Some(tx_hash) = stream.next().await {
+        let tx = match client.get_transaction(tx_hash).await {
+            Ok(Some(tx)) => tx,
+            Ok(None) => continue,
+            Err(e) => {
+                // A single failed lookup doesn't mean the socket is dead;
+                // don't let `?` kill this whole watcher over one hiccup.
+                log::warn!("get_transaction failed for {:?}: {e}", tx_hash);
+                continue;
+            }
+        };
+
+        let Some(to) = tx.to else { continue };
+        if !watched_pools.contains(&to) {
+            continue;
+        }
+
+        match trace_pending_tx(&client, &tx, &watched_pools).await {
+            Ok(Some(opportunity)) => {
+                if let Some(signal) = detect_opportunity(&opportunity) {
+                    println!(
+                        "⚡ Predicted opportunity on pool {:?} from pending tx {:?} ({})",
+                        opportunity.pool, opportunity.tx_hash, signal
+                    );
+
+                    match build_front_run(&client, &token_cache, &opportunity).await {
+                        Ok(Some((token, amount, data))) => {
+                            let fees = match gas::estimate_fees(&client).await {
+                                Ok(fees) => fees,
+                                Err(e) => {
+                                    log::warn!("fee estimation failed, skipping opportunity: {e}");
+                                    continue;
+                                }
+                            };
+                            let contract = contract_rx.borrow().clone();
+                            if let Err(e) =
+                                execution::execute_arbitrage(&contract, token, amount, data, fees)
+                                    .await
+                            {
+                                log::error!("executeArbitrage submission failed: {e}");
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => log::warn!(
+                            "failed to resolve pool tokens for {:?}: {e}",
+                            opportunity.pool
+                        ),
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("stateDiff trace failed for {:?}: {e}", tx_hash),
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves the pool's traded token and sizes the front-run off its
+/// predicted post-tx depth, building the `executeArbitrage` calldata.
+async fn build_front_run(
+    client: &Arc<Provider<Ws>>,
+    token_cache: &TokenCache,
+    opportunity: &PendingOpportunity,
+) -> Result<Option<(Address, U256, Bytes)>> {
+    let (token0, _token1) = pools::pool_tokens(client, token_cache, opportunity.pool).await?;
+
+    let Some(amount) = opportunity
+        .post
+        .reserve0
+        .checked_div(U256::from(TRADE_SIZE_DEPTH_DIVISOR))
+        .filter(|amount| !amount.is_zero())
+    else {
+        return Ok(None);
+    };
+
+    let data = ethers::abi::encode(&[ethers::abi::Token::Address(opportunity.pool)]).into();
+
+    Ok(Some((token0, amount, data)))
+}
+
+/// Runs `debug_traceCall` with a prestate/stateDiff tracer against the
+/// pending block and reconstructs the pre/post reserves for the touched pool.
+async fn trace_pending_tx(
+    client: &Provider<Ws>,
+    tx: &Transaction,
+    watched_pools: &WatchedPools,
+) -> Result<Option<PendingOpportunity>> {
+    let call = json!({
+        "from": tx.from,
+        "to": tx.to,
+        "data": tx.input,
+        "value": tx.value,
+        "gas": tx.gas,
+    });
+    let tracer_opts = json!({
+        "tracer": "prestateTracer",
+        "tracerConfig": { "diffMode": true },
+    });
+
+    let trace: serde_json::Value = client
+        .request("debug_traceCall", (call, "pending", tracer_opts))
+        .await?;
+
+    let Some(pool) = tx.to.filter(|to| watched_pools.contains(to)) else {
+        return Ok(None);
+    };
+
+    let pre = extract_reserves(&trace, pool, "pre");
+    let post = extract_reserves(&trace, pool, "post");
+
+    match (pre, post) {
+        (Some(pre), Some(post)) => Ok(Some(PendingOpportunity {
+            pool,
+            tx_hash: tx.hash,
+            pre,
+            post,
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// Pulls the reserves storage slot for `pool` out of a `prestateTracer`
+/// diffMode result's `pre` or `post` side.
+fn extract_reserves(trace: &serde_json::Value, pool: Address, side: &str) -> Option<Reserves> {
+    let slot_key = format!("{:#066x}", U256::from(RESERVES_SLOT));
+    let storage = trace
+        .get(side)?
+        .get(format!("{:?}", pool))?
+        .get("storage")?
+        .get(&slot_key)?
+        .as_str()?;
+    let slot: H256 = storage.parse().ok()?;
+    Some(decode_reserves_slot(slot))
+}
+
+/// Compares pre/post reserves and reports whether the implied price move
+/// reaches `THRESHOLD_BPS`; profitability is not estimated.
+fn detect_opportunity(opportunity: &PendingOpportunity) -> Option<String> {
+    const THRESHOLD_BPS: u64 = 30; // 0.30%
+
+    let pre_price = price_bps(&opportunity.pre)?;
+    let post_price = price_bps(&opportunity.post)?;
+
+    let delta = u256_abs_diff(pre_price, post_price);
+    let gap_bps = delta
+        .saturating_mul(U256::from(10_000u64))
+        / pre_price.max(U256::one());
+
+    if gap_bps >= U256::from(THRESHOLD_BPS) {
+        Some(format!("price moved {gap_bps} bps"))
+    } else {
+        None
+    }
+}
+
+/// token1-per-token0 price scaled by 1e6, used only for relative comparison.
+/// Kept as `U256` (rather than downcast to `u64`/`u128`) since a thin pool or
+/// a large token-decimals mismatch can scale well past either range, and
+/// `as_u64`/`as_u128` panic on overflow instead of truncating.
+fn price_bps(r: &Reserves) -> Option<U256> {
+    if r.reserve0.is_zero() {
+        return None;
+    }
+    Some(r.reserve1.saturating_mul(U256::from(1_000_000)) / r.reserve0)
+}
+
+fn u256_abs_diff(a: U256, b: U256) -> U256 {
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs (blockTimestampLast, reserve1, reserve0) into a storage slot the
+    /// way solc lays out `uint32`/`uint112`/`uint112`, for round-tripping
+    /// through `decode_reserves_slot`.
+    fn pack_reserves_slot(timestamp: u32, reserve1: u128, reserve0: u128) -> H256 {
+        let mut bytes = [0u8; 32];
+        bytes[0..4].copy_from_slice(&timestamp.to_be_bytes());
+        bytes[4..18].copy_from_slice(&reserve1.to_be_bytes()[2..16]);
+        bytes[18..32].copy_from_slice(&reserve0.to_be_bytes()[2..16]);
+        H256::from(bytes)
+    }
+
+    #[test]
+    fn decode_reserves_slot_unpacks_reserve0_and_reserve1() {
+        let slot = pack_reserves_slot(1_700_000_000, 2_000_000, 1_000_000);
+        let reserves = decode_reserves_slot(slot);
+        assert_eq!(reserves.reserve0, U256::from(1_000_000u64));
+        assert_eq!(reserves.reserve1, U256::from(2_000_000u64));
+    }
+
+    #[test]
+    fn decode_reserves_slot_handles_zero_reserves() {
+        let slot = pack_reserves_slot(0, 0, 0);
+        let reserves = decode_reserves_slot(slot);
+        assert_eq!(reserves.reserve0, U256::zero());
+        assert_eq!(reserves.reserve1, U256::zero());
+    }
+
+    #[test]
+    fn price_bps_is_none_for_zero_reserve0() {
+        let reserves = Reserves {
+            reserve0: U256::zero(),
+            reserve1: U256::from(100u64),
+        };
+        assert_eq!(price_bps(&reserves), None);
+    }
+
+    #[test]
+    fn price_bps_scales_reserve1_over_reserve0() {
+        let reserves = Reserves {
+            reserve0: U256::from(2u64),
+            reserve1: U256::from(1u64),
+        };
+        assert_eq!(price_bps(&reserves), Some(U256::from(500_000u64)));
+    }
+
+    fn opportunity_with(pre: Reserves, post: Reserves) -> PendingOpportunity {
+        PendingOpportunity {
+            pool: Address::zero(),
+            tx_hash: H256::zero(),
+            pre,
+            post,
+        }
+    }
+
+    #[test]
+    fn detect_opportunity_none_below_threshold() {
+        let pre = Reserves {
+            reserve0: U256::from(1_000u64),
+            reserve1: U256::from(1_000u64),
+        };
+        let post = Reserves {
+            reserve0: U256::from(1_000u64),
+            reserve1: U256::from(1_001u64),
+        };
+        assert_eq!(detect_opportunity(&opportunity_with(pre, post)), None);
+    }
+
+    #[test]
+    fn detect_opportunity_some_above_threshold() {
+        let pre = Reserves {
+            reserve0: U256::from(1_000u64),
+            reserve1: U256::from(1_000u64),
+        };
+        let post = Reserves {
+            reserve0: U256::from(1_000u64),
+            reserve1: U256::from(1_100u64),
+        };
+        assert!(detect_opportunity(&opportunity_with(pre, post)).is_some());
+    }
+}
@@ -0,0 +1,332 @@
+use ethers::prelude::*;
+use ethers::providers::{Provider, Ws};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use anyhow::Result;
+
+use crate::execution::{self, ContractWatch};
+use crate::gas;
+
+abigen!(
+    UniswapV2Pair,
+    r#"[
+        event Sync(uint112 reserve0, uint112 reserve1)
+        event Swap(address indexed sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address indexed to)
+        function token0() view returns (address)
+        function token1() view returns (address)
+    ]"#
+);
+
+/// Minimum relative price gap (in basis points) across two pools before an
+/// arbitrage is considered worth executing.
+const PRICE_GAP_THRESHOLD_BPS: u64 = 25;
+
+/// Fraction of the shallower pool's depth (in the shared token) to size a
+/// trade at, so a single arb doesn't walk the price past the gap it's
+/// chasing.
+const TRADE_SIZE_DEPTH_DIVISOR: u64 = 100; // 1%
+
+/// Live reserves per pool, updated from `Sync` events as they stream in.
+pub type ReserveMap = Arc<RwLock<HashMap<Address, (U256, U256)>>>;
+
+/// `token0`/`token1` per pool, resolved once via `eth_call` and cached since
+/// a pair's tokens never change.
+pub type TokenCache = Arc<RwLock<HashMap<Address, (Address, Address)>>>;
+
+/// An arbitrage opportunity opened by a reserve update: buy `token` on
+/// `buy_pool`, sell it on `sell_pool`, sized to `amount`.
+#[derive(Debug, Clone)]
+pub struct ArbOpportunity {
+    pub buy_pool: Address,
+    pub sell_pool: Address,
+    pub token: Address,
+    pub amount: U256,
+    pub gap_bps: U256,
+}
+
+/// Subscribes to `Swap`/`Sync` logs for the given pool addresses, keeps
+/// `reserves` up to date, and reports opportunities opened whenever a pool's
+/// reserves move relative to the others.
+pub async fn stream_pool_events(
+    client: Arc<Provider<Ws>>,
+    contract_rx: ContractWatch,
+    pools: Vec<Address>,
+    reserves: ReserveMap,
+) -> Result<()> {
+    let filter = Filter::new()
+        .address(pools.clone())
+        .topic0(vec![SyncFilter::signature(), SwapFilter::signature()]);
+
+    let mut stream = client.subscribe_logs(&filter).await?;
+    println!("🔄 Streaming Swap/Sync events for {} pool(s)...", pools.len());
+
+    let token_cache: TokenCache = Arc::new(RwLock::new(HashMap::new()));
+
+    while let Some(log) = stream.next().await {
+        let pool = log.address;
+        let Ok(decoded) = <UniswapV2PairEvents as EthLogDecode>::decode_log(&log.clone().into())
+        else {
+            continue;
+        };
+
+        match decoded {
+            UniswapV2PairEvents::SyncFilter(sync) => {
+                let new_reserves = (U256::from(sync.reserve0), U256::from(sync.reserve1));
+                reserves.write().await.insert(pool, new_reserves);
+
+                match detect_cross_pool_gap(&client, &token_cache, &reserves, pool).await {
+                    Ok(Some(opportunity)) => {
+                        println!(
+                            "⚡ Cross-pool gap detected: buy {:?} on {:?}, sell on {:?} ({} bps, amount {})",
+                            opportunity.token,
+                            opportunity.buy_pool,
+                            opportunity.sell_pool,
+                            opportunity.gap_bps,
+                            opportunity.amount
+                        );
+
+                        let data = build_arbitrage_calldata(&opportunity);
+                        let fees = match gas::estimate_fees(&client).await {
+                            Ok(fees) => fees,
+                            Err(e) => {
+                                // A transient fee-history error doesn't mean
+                                // the socket died; don't let `?` kill this
+                                // whole watcher over one hiccup.
+                                log::warn!("fee estimation failed, skipping opportunity: {e}");
+                                continue;
+                            }
+                        };
+                        // Re-borrow the live contract on every submission: it
+                        // is rebuilt in place whenever the WS provider
+                        // reconnects, so a stale Arc captured once at spawn
+                        // time would keep sending over a dead socket after
+                        // the first reconnect.
+                        let contract = contract_rx.borrow().clone();
+                        if let Err(e) = execution::execute_arbitrage(
+                            &contract,
+                            opportunity.token,
+                            opportunity.amount,
+                            data,
+                            fees,
+                        )
+                        .await
+                        {
+                            log::error!("executeArbitrage submission failed: {e}");
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("failed to resolve pool tokens for {:?}: {e}", pool),
+                }
+            }
+            UniswapV2PairEvents::SwapFilter(swap) => {
+                println!(
+                    "🔀 Swap on {:?}: sender {:?} -> {:?}",
+                    pool, swap.sender, swap.to
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compares the updated pool's price against every other tracked pool,
+/// resolves the token shared between the widest-gap pair, and sizes a trade
+/// off the shallower side's depth in that token.
+async fn detect_cross_pool_gap(
+    client: &Arc<Provider<Ws>>,
+    token_cache: &TokenCache,
+    reserves: &ReserveMap,
+    updated: Address,
+) -> Result<Option<ArbOpportunity>> {
+    let snapshot = reserves.read().await.clone();
+    let Some(&(r0, r1)) = snapshot.get(&updated) else {
+        return Ok(None);
+    };
+    let Some(updated_price) = price_bps(r0, r1) else {
+        return Ok(None);
+    };
+
+    let mut best: Option<(Address, Address, U256)> = None; // (buy_pool, sell_pool, gap_bps)
+    for (&pool, &(other_r0, other_r1)) in snapshot.iter() {
+        if pool == updated {
+            continue;
+        }
+        let Some(other_price) = price_bps(other_r0, other_r1) else {
+            continue;
+        };
+
+        let gap_bps = u256_abs_diff(updated_price, other_price)
+            .saturating_mul(U256::from(10_000u64))
+            / updated_price.max(U256::one());
+        if gap_bps < U256::from(PRICE_GAP_THRESHOLD_BPS) {
+            continue;
+        }
+
+        let (buy_pool, sell_pool) = if updated_price < other_price {
+            (updated, pool)
+        } else {
+            (pool, updated)
+        };
+
+        if best.as_ref().map_or(true, |(_, _, best_gap)| gap_bps > *best_gap) {
+            best = Some((buy_pool, sell_pool, gap_bps));
+        }
+    }
+
+    let Some((buy_pool, sell_pool, gap_bps)) = best else {
+        return Ok(None);
+    };
+
+    let (buy_token0, buy_token1) = pool_tokens(client, token_cache, buy_pool).await?;
+    let (sell_token0, sell_token1) = pool_tokens(client, token_cache, sell_pool).await?;
+
+    let Some(token) = shared_token((buy_token0, buy_token1), (sell_token0, sell_token1)) else {
+        return Ok(None);
+    };
+
+    let (buy_r0, buy_r1) = snapshot[&buy_pool];
+    let (sell_r0, sell_r1) = snapshot[&sell_pool];
+    let buy_depth = reserve_for_token(buy_token0, (buy_r0, buy_r1), token);
+    let sell_depth = reserve_for_token(sell_token0, (sell_r0, sell_r1), token);
+
+    let Some(amount) = buy_depth
+        .min(sell_depth)
+        .checked_div(U256::from(TRADE_SIZE_DEPTH_DIVISOR))
+        .filter(|amount| !amount.is_zero())
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(ArbOpportunity {
+        buy_pool,
+        sell_pool,
+        token,
+        amount,
+        gap_bps,
+    }))
+}
+
+/// Resolves (and caches) `token0`/`token1` for a pool via `eth_call`.
+pub(crate) async fn pool_tokens(
+    client: &Arc<Provider<Ws>>,
+    cache: &TokenCache,
+    pool: Address,
+) -> Result<(Address, Address)> {
+    if let Some(&tokens) = cache.read().await.get(&pool) {
+        return Ok(tokens);
+    }
+
+    let pair = UniswapV2Pair::new(pool, client.clone());
+    let token0 = pair.token_0().call().await?;
+    let token1 = pair.token_1().call().await?;
+
+    cache.write().await.insert(pool, (token0, token1));
+    Ok((token0, token1))
+}
+
+/// Returns the token address the two pools have in common, if any.
+fn shared_token(a: (Address, Address), b: (Address, Address)) -> Option<Address> {
+    [a.0, a.1]
+        .into_iter()
+        .find(|token| *token == b.0 || *token == b.1)
+}
+
+/// Picks the reserve that corresponds to `token`, given the pool's `token0`
+/// address and its `(reserve0, reserve1)` tuple.
+fn reserve_for_token(token0: Address, reserves: (U256, U256), token: Address) -> U256 {
+    if token == token0 {
+        reserves.0
+    } else {
+        reserves.1
+    }
+}
+
+/// Encodes the buy/sell pool pair into the calldata `executeArbitrage`
+/// expects; the aggregator contract routes the borrowed `token`/`amount` in
+/// on `buy_pool` and back out through `sell_pool`.
+fn build_arbitrage_calldata(opportunity: &ArbOpportunity) -> Bytes {
+    ethers::abi::encode(&[
+        ethers::abi::Token::Address(opportunity.buy_pool),
+        ethers::abi::Token::Address(opportunity.sell_pool),
+    ])
+    .into()
+}
+
+/// token1-per-token0 price scaled by 1e6, used only for relative comparison.
+/// Kept as `U256` (rather than downcast to `u64`) since a thin pool or a
+/// large token-decimals mismatch can scale well past `u64::MAX`, and
+/// `as_u64` panics on overflow instead of truncating.
+fn price_bps(reserve0: U256, reserve1: U256) -> Option<U256> {
+    if reserve0.is_zero() {
+        return None;
+    }
+    Some(reserve1.saturating_mul(U256::from(1_000_000)) / reserve0)
+}
+
+fn u256_abs_diff(a: U256, b: U256) -> U256 {
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(byte: u8) -> Address {
+        Address::from_low_u64_be(byte as u64)
+    }
+
+    #[test]
+    fn shared_token_finds_common_token0() {
+        let a = (addr(1), addr(2));
+        let b = (addr(1), addr(3));
+        assert_eq!(shared_token(a, b), Some(addr(1)));
+    }
+
+    #[test]
+    fn shared_token_finds_common_token1() {
+        let a = (addr(1), addr(2));
+        let b = (addr(3), addr(2));
+        assert_eq!(shared_token(a, b), Some(addr(2)));
+    }
+
+    #[test]
+    fn shared_token_none_when_disjoint() {
+        let a = (addr(1), addr(2));
+        let b = (addr(3), addr(4));
+        assert_eq!(shared_token(a, b), None);
+    }
+
+    #[test]
+    fn reserve_for_token_picks_reserve0_for_token0() {
+        let token0 = addr(1);
+        let reserves = (U256::from(100u64), U256::from(200u64));
+        assert_eq!(reserve_for_token(token0, reserves, token0), U256::from(100u64));
+    }
+
+    #[test]
+    fn reserve_for_token_picks_reserve1_for_other_token() {
+        let token0 = addr(1);
+        let token1 = addr(2);
+        let reserves = (U256::from(100u64), U256::from(200u64));
+        assert_eq!(reserve_for_token(token0, reserves, token1), U256::from(200u64));
+    }
+
+    #[test]
+    fn price_bps_is_none_for_zero_reserve0() {
+        assert_eq!(price_bps(U256::zero(), U256::from(100u64)), None);
+    }
+
+    #[test]
+    fn price_bps_scales_reserve1_over_reserve0() {
+        assert_eq!(
+            price_bps(U256::from(2u64), U256::from(1u64)),
+            Some(U256::from(500_000u64))
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use ethers::providers::{Middleware, Provider, ProviderError, Ws};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use anyhow::{anyhow, Result};
+
+/// Endpoints the connection manager will cycle through on failure, in order:
+/// primary WS, then optional backup WS.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    pub primary_ws: String,
+    pub backup_ws: Option<String>,
+}
+
+impl Endpoints {
+    /// Reads `ETH_RPC_URL` and `ETH_RPC_URL_BACKUP` from the environment.
+    pub fn from_env() -> Self {
+        Self {
+            primary_ws: env::var("ETH_RPC_URL").unwrap_or_else(|_| "ws://localhost:8545".to_string()),
+            backup_ws: env::var("ETH_RPC_URL_BACKUP").ok(),
+        }
+    }
+
+    fn ws_candidates(&self) -> Vec<&str> {
+        let mut candidates = vec![self.primary_ws.as_str()];
+        if let Some(backup) = &self.backup_ws {
+            candidates.push(backup);
+        }
+        candidates
+    }
+}
+
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Broadcasts a freshly (re)connected `Provider<Ws>` every time the
+/// connection manager re-establishes a socket, so long-running subscription
+/// loops can pick it up and resubscribe.
+pub type ProviderWatch = watch::Receiver<Arc<Provider<Ws>>>;
+
+/// Connects to the first reachable WS endpoint and spawns a background task
+/// that watches the connection, reconnects with exponential backoff on
+/// failure, and publishes each new client on the returned watch channel.
+pub async fn connect(endpoints: Endpoints) -> Result<ProviderWatch> {
+    let initial = connect_any_ws(&endpoints).await?;
+    let (tx, rx) = watch::channel(Arc::new(initial));
+
+    tokio::spawn(async move {
+        loop {
+            // Wait until the current provider looks dead;
+            // `wait_for_disconnect` polls a cheap RPC call so it works
+            // uniformly across ethers-rs versions.
+            let current = tx.borrow().clone();
+            wait_for_disconnect(&current).await;
+            log::warn!("WS provider disconnected, reconnecting...");
+
+            let mut backoff = BASE_BACKOFF;
+            loop {
+                match connect_any_ws(&endpoints).await {
+                    Ok(provider) => {
+                        log::info!("WS provider reconnected");
+                        let _ = tx.send(Arc::new(provider));
+                        break;
+                    }
+                    Err(e) => {
+                        log::error!("reconnect attempt failed: {e}");
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Tries each configured WS endpoint in order and returns the first one that
+/// connects.
+async fn connect_any_ws(endpoints: &Endpoints) -> Result<Provider<Ws>> {
+    let mut last_err: Option<ProviderError> = None;
+    for url in endpoints.ws_candidates() {
+        match Provider::<Ws>::connect(url).await {
+            Ok(provider) => return Ok(provider),
+            Err(e) => {
+                log::warn!("failed to connect to {url}: {e}");
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(anyhow!(
+        "all WS endpoints unreachable: {}",
+        last_err.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+/// Polls the provider with a cheap call until it errors, signalling the
+/// socket has died.
+async fn wait_for_disconnect(provider: &Provider<Ws>) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        if provider.get_block_number().await.is_err() {
+            return;
+        }
+    }
+}